@@ -1,3 +1,5 @@
+mod ci;
+
 use log::debug;
 
 use anyhow::anyhow;
@@ -27,6 +29,8 @@ Options:
     --path PATH                  Upload the documentation to the specified remote path (defaults to $TRAVIS_BRANCH/)
     --clobber-index              Delete `index.html` from repo
     --target TRIPLE              Fetch the documentation for the target triple
+    --ci PROVIDER                Which CI provider to detect branch/PR/slug from:
+                                 auto, travis, github, gitlab, or git [default: auto]
 ";
 
 #[derive(Deserialize)]
@@ -39,6 +43,7 @@ pub struct Options {
     flag_path: Option<String>,
     flag_clobber_index: bool,
     flag_target: Option<String>,
+    flag_ci: String,
 }
 
 fn execute(options: Options, _: &Config) -> CliResult {
@@ -58,14 +63,17 @@ fn execute(options: Options, _: &Config) -> CliResult {
         options.flag_branch
     };
 
-    let branch = env::var("TRAVIS_BRANCH").expect("$TRAVIS_BRANCH not set");
+    let provider = ci::Provider::parse(&options.flag_ci)
+        .map_err(|e| CliError::new(anyhow!(e), 1))?;
+    let ci_info = ci::detect(provider).map_err(|e| CliError::new(anyhow!(e), 1))?;
+
+    let branch = ci_info.branch;
     if !branches.contains(&branch) {
         println!("Skipping branch {}", branch);
         return Ok(());
     }
 
-    let pull_request = env::var("TRAVIS_PULL_REQUEST").expect("$TRAVIS_PULL_REQUEST not set");
-    if pull_request != "false" {
+    if ci_info.is_pull_request {
         println!("Skipping PR");
         return Ok(());
     }
@@ -74,7 +82,7 @@ fn execute(options: Options, _: &Config) -> CliResult {
 
     // TODO FEAT: Allow passing origin string
     let token = options.flag_token.or_else(|| env::var("GH_TOKEN").ok());
-    let slug = env::var("TRAVIS_REPO_SLUG").expect("$TRAVIS_REPO_SLUG not set");
+    let slug = ci_info.repo_slug;
     let origin = if let Some(token) = token {
         format!("https://{}@github.com/{}.git", token, slug)
     } else {
@@ -0,0 +1,151 @@
+//! Detection of the branch, PR status, and repo slug across CI providers
+//! (and plain local git, for when this is run outside of CI entirely).
+
+use std::env;
+use std::process::Command;
+
+/// Normalized CI state, regardless of which provider produced it.
+pub struct CiInfo {
+    pub branch: String,
+    pub is_pull_request: bool,
+    pub repo_slug: String,
+}
+
+/// Which CI provider to probe for. `Auto` tries each in turn and falls
+/// back to local git if none match.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Provider {
+    Auto,
+    Travis,
+    GitHub,
+    GitLab,
+    Git,
+}
+
+impl Provider {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "auto" => Ok(Provider::Auto),
+            "travis" => Ok(Provider::Travis),
+            "github" => Ok(Provider::GitHub),
+            "gitlab" => Ok(Provider::GitLab),
+            "git" => Ok(Provider::Git),
+            other => Err(format!("unknown CI provider: {}", other)),
+        }
+    }
+}
+
+/// Detect the current CI environment, returning a normalized `CiInfo`.
+///
+/// When `provider` is `Auto`, each provider is probed in turn (Travis,
+/// GitHub Actions, GitLab CI, then plain local git) and the first one
+/// whose required environment variables are present wins.
+pub fn detect(provider: Provider) -> Result<CiInfo, String> {
+    match provider {
+        Provider::Auto => travis()
+            .or_else(|_| github())
+            .or_else(|_| gitlab())
+            .or_else(|_| git()),
+        Provider::Travis => travis(),
+        Provider::GitHub => github(),
+        Provider::GitLab => gitlab(),
+        Provider::Git => git(),
+    }
+}
+
+fn travis() -> Result<CiInfo, String> {
+    let branch = env::var("TRAVIS_BRANCH").map_err(|_| "$TRAVIS_BRANCH not set".to_string())?;
+    let pull_request = env::var("TRAVIS_PULL_REQUEST")
+        .map_err(|_| "$TRAVIS_PULL_REQUEST not set".to_string())?;
+    let repo_slug =
+        env::var("TRAVIS_REPO_SLUG").map_err(|_| "$TRAVIS_REPO_SLUG not set".to_string())?;
+    Ok(CiInfo {
+        branch,
+        is_pull_request: pull_request != "false",
+        repo_slug,
+    })
+}
+
+fn github() -> Result<CiInfo, String> {
+    let branch = env::var("GITHUB_REF_NAME")
+        .or_else(|_| {
+            env::var("GITHUB_REF").map(|r| {
+                r.strip_prefix("refs/heads/")
+                    .or_else(|| r.strip_prefix("refs/tags/"))
+                    .map(|s| s.to_string())
+                    .unwrap_or(r)
+            })
+        })
+        .map_err(|_| "$GITHUB_REF_NAME/$GITHUB_REF not set".to_string())?;
+    let event_name =
+        env::var("GITHUB_EVENT_NAME").map_err(|_| "$GITHUB_EVENT_NAME not set".to_string())?;
+    let repo_slug =
+        env::var("GITHUB_REPOSITORY").map_err(|_| "$GITHUB_REPOSITORY not set".to_string())?;
+    Ok(CiInfo {
+        branch,
+        // pull_request_target also checks out PR-triggered content (e.g. for
+        // forks granted access to secrets), so treat it as a PR too.
+        is_pull_request: event_name == "pull_request" || event_name == "pull_request_target",
+        repo_slug,
+    })
+}
+
+fn gitlab() -> Result<CiInfo, String> {
+    let branch = env::var("CI_COMMIT_REF_NAME")
+        .map_err(|_| "$CI_COMMIT_REF_NAME not set".to_string())?;
+    let repo_slug =
+        env::var("CI_PROJECT_PATH").map_err(|_| "$CI_PROJECT_PATH not set".to_string())?;
+    // GitLab sets CI_MERGE_REQUEST_ID only when building a merge request pipeline.
+    let is_pull_request = env::var("CI_MERGE_REQUEST_ID").is_ok();
+    Ok(CiInfo {
+        branch,
+        is_pull_request,
+        repo_slug,
+    })
+}
+
+fn git() -> Result<CiInfo, String> {
+    let branch = git_output(&["rev-parse", "--abbrev-ref", "HEAD"])?;
+    let origin = git_output(&["config", "--get", "remote.origin.url"])?;
+    let repo_slug = slug_from_origin(&origin)
+        .ok_or_else(|| format!("could not parse a GitHub slug from origin {:?}", origin))?;
+    Ok(CiInfo {
+        branch,
+        is_pull_request: false,
+        repo_slug,
+    })
+}
+
+fn git_output(args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .args(args)
+        .output()
+        .map_err(|e| format!("failed to run git {:?}: {}", args, e))?;
+    if !output.status.success() {
+        return Err(format!("git {:?} exited with {}", args, output.status));
+    }
+    String::from_utf8(output.stdout)
+        .map(|s| s.trim().to_string())
+        .map_err(|e| format!("git {:?} produced invalid UTF-8: {}", args, e))
+}
+
+/// Pull an `owner/repo` slug out of an `origin` remote URL, supporting
+/// both the `git@github.com:owner/repo.git` and
+/// `https://github.com/owner/repo.git` forms.
+fn slug_from_origin(origin: &str) -> Option<String> {
+    let origin = origin.trim().trim_end_matches(".git");
+    let path = if let Some(rest) = origin.strip_prefix("git@github.com:") {
+        rest
+    } else if let Some(rest) = origin.strip_prefix("https://github.com/") {
+        rest
+    } else if let Some(rest) = origin.strip_prefix("http://github.com/") {
+        rest
+    } else {
+        return None;
+    };
+    if path.is_empty() {
+        None
+    } else {
+        Some(path.to_string())
+    }
+}